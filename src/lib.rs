@@ -2,26 +2,286 @@ extern crate libc;
 
 use std::io;
 use std::io::Error;
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+use std::time::Duration;
+
+/// Converts an optional wait timeout into the millisecond value expected by `epoll_wait`
+///
+/// `None` maps to -1 (block indefinitely) and `Duration::ZERO` maps to 0 (return immediately).
+/// Any other duration is rounded up to at least 1ms so short sleeps aren't silently truncated to
+/// a non-blocking poll, and saturates at `i32::MAX` for durations that don't fit.
+fn duration_to_timeout_ms(timeout: Option<Duration>) -> i32 {
+    let timeout = match timeout {
+        None => return -1,
+        Some(timeout) => timeout,
+    };
+    if timeout.is_zero() {
+        return 0;
+    }
+    let millis = timeout.as_millis().max(1);
+    millis.min(i32::MAX as u128) as i32
+}
+
+/// An owned epoll instance
+///
+/// `Epoll` wraps the epoll file descriptor in an `OwnedFd`, so the descriptor is closed
+/// automatically when the `Epoll` value is dropped. It is built on top of the free functions in
+/// this crate and exists so callers don't have to track and `close` the raw file descriptor
+/// themselves.
+pub struct Epoll {
+    fd: OwnedFd,
+}
+
+impl Epoll {
+    /// Create a new epoll instance
+    ///
+    /// # Arguments
+    ///
+    /// `flags`: flags to pass to `libc::epoll_create1`. See [`epoll_create1`] for details.
+    pub fn new(flags: EpollCreateFlags) -> io::Result<Epoll> {
+        let fd = epoll_create1(flags)?;
+        Ok(Epoll {
+            fd: unsafe { OwnedFd::from_raw_fd(fd) },
+        })
+    }
+
+    /// Register `fd` with this epoll instance for the events described by `event`
+    pub fn add(&self, fd: RawFd, event: EpollEvent) -> io::Result<()> {
+        epoll_ctl(self.fd.as_raw_fd(), EpollOp::Add, fd, event)
+    }
+
+    /// Change the event settings associated with `fd` on this epoll instance
+    pub fn modify(&self, fd: RawFd, event: EpollEvent) -> io::Result<()> {
+        epoll_ctl(self.fd.as_raw_fd(), EpollOp::Mod, fd, event)
+    }
+
+    /// Remove `fd` from the interest list of this epoll instance
+    pub fn delete(&self, fd: RawFd) -> io::Result<()> {
+        epoll_ctl(self.fd.as_raw_fd(), EpollOp::Del, fd, EpollEvent::empty())
+    }
+
+    /// Wait for I/O events on this epoll instance
+    ///
+    /// # Arguments
+    ///
+    /// `events`: reusable buffer used to return the ready events. Its length is used as
+    /// `max_events`, so it can never disagree with the count passed to the underlying call.
+    ///
+    /// `timeout`: how long to block waiting for events. `None` blocks indefinitely until an event
+    /// occurs, and `Duration::ZERO` returns immediately, even if no events are available.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the sub-slice of `events` that was filled in with ready events.
+    pub fn wait<'a>(
+        &self,
+        events: &'a mut [EpollEvent],
+        timeout: Option<Duration>,
+    ) -> io::Result<&'a [EpollEvent]> {
+        let timeout_ms = duration_to_timeout_ms(timeout);
+        let ready = epoll_wait(self.fd.as_raw_fd(), events, events.len() as i32, timeout_ms)?;
+        Ok(&events[..ready])
+    }
+}
+
+impl AsRawFd for Epoll {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl AsFd for Epoll {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+macro_rules! bitflags_i32 {
+    (
+        $(#[$outer:meta])*
+        pub struct $name:ident : $inner:ty {
+            $($(#[$inner_meta:meta])* const $flag:ident = $value:expr;)*
+        }
+    ) => {
+        $(#[$outer])*
+        #[derive(Clone, Copy, Default, PartialEq, Eq)]
+        pub struct $name($inner);
+
+        impl $name {
+            $($(#[$inner_meta])* pub const $flag: $name = $name($value);)*
+
+            /// Returns an empty set of flags
+            pub const fn empty() -> $name {
+                $name(0)
+            }
+
+            /// Returns the raw bits of this set of flags
+            pub const fn bits(self) -> $inner {
+                self.0
+            }
+
+            /// Returns `true` if `self` contains all of the flags in `other`
+            pub const fn contains(self, other: $name) -> bool {
+                self.0 & other.0 == other.0
+            }
+        }
+
+        impl std::ops::BitOr for $name {
+            type Output = $name;
+
+            fn bitor(self, rhs: $name) -> $name {
+                $name(self.0 | rhs.0)
+            }
+        }
+
+        impl std::ops::BitOrAssign for $name {
+            fn bitor_assign(&mut self, rhs: $name) {
+                self.0 |= rhs.0;
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let mut first = true;
+                let mut known_bits: $inner = 0;
+                write!(f, "{}(", stringify!($name))?;
+                $(
+                    known_bits |= $name::$flag.0;
+                    if self.contains($name::$flag) {
+                        if !first {
+                            write!(f, " | ")?;
+                        }
+                        write!(f, "{}", stringify!($flag))?;
+                        first = false;
+                    }
+                )*
+                let unknown_bits = self.0 & !known_bits;
+                if unknown_bits != 0 {
+                    if !first {
+                        write!(f, " | ")?;
+                    }
+                    write!(f, "0x{:x}", unknown_bits)?;
+                    first = false;
+                }
+                if first {
+                    write!(f, "0x{:x}", self.0)?;
+                }
+                write!(f, ")")
+            }
+        }
+    };
+}
+
+bitflags_i32! {
+    /// Flags describing the events to watch for, or that occurred, on a file descriptor
+    ///
+    /// These correspond to the `EPOLL*` event constants accepted by `libc::epoll_ctl` and
+    /// returned by `libc::epoll_wait`.
+    pub struct EpollFlags : i32 {
+        /// The associated file is available for read operations
+        const EPOLLIN = libc::EPOLLIN;
+        /// The associated file is available for write operations
+        const EPOLLOUT = libc::EPOLLOUT;
+        /// There is urgent data available for read operations
+        const EPOLLPRI = libc::EPOLLPRI;
+        /// Error condition happened on the associated file descriptor
+        const EPOLLERR = libc::EPOLLERR;
+        /// Hang up happened on the associated file descriptor
+        const EPOLLHUP = libc::EPOLLHUP;
+        /// Stream socket peer closed the connection, or shut down writing half of the connection
+        const EPOLLRDHUP = libc::EPOLLRDHUP;
+        /// Requests edge-triggered notification for the associated file descriptor
+        const EPOLLET = libc::EPOLLET;
+        /// Requests one-shot notification for the associated file descriptor
+        const EPOLLONESHOT = libc::EPOLLONESHOT;
+        /// Ensures that only one epoll instance wakes up for the associated file descriptor
+        const EPOLLEXCLUSIVE = libc::EPOLLEXCLUSIVE;
+        /// Keeps the system from suspending while the event is pending
+        const EPOLLWAKEUP = libc::EPOLLWAKEUP;
+    }
+}
+
+bitflags_i32! {
+    /// Flags accepted by `libc::epoll_create1`
+    pub struct EpollCreateFlags : i32 {
+        /// Set the close-on-exec flag on the new file descriptor
+        const EPOLL_CLOEXEC = libc::EPOLL_CLOEXEC;
+    }
+}
+
+/// Operation to be performed on the interest list of an epoll instance
+///
+/// See the `op` argument of [`epoll_ctl`] and https://man7.org/linux/man-pages/man2/epoll_ctl.2.html.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EpollOp {
+    /// Register the target file descriptor with the epoll instance
+    Add,
+    /// Change the settings associated with the target file descriptor
+    Mod,
+    /// Remove the target file descriptor from the epoll instance
+    Del,
+}
+
+impl EpollOp {
+    fn as_raw(self) -> i32 {
+        match self {
+            EpollOp::Add => libc::EPOLL_CTL_ADD,
+            EpollOp::Mod => libc::EPOLL_CTL_MOD,
+            EpollOp::Del => libc::EPOLL_CTL_DEL,
+        }
+    }
+}
 
 /// Describes an epoll event
-#[repr(C)]
-#[cfg_attr(target_arch = "x86_64", repr(packed))]
-#[derive(Clone, Copy, Debug)]
-pub struct EpollEvent {
-    ///  a bit mask composed by ORing together zero or more event types
-    pub events: i32,
-    /// user data variable
-    pub data: u64,
+///
+/// `EpollEvent` is a transparent wrapper around `libc::epoll_event`, so it always has the same
+/// size, alignment, and field layout as the kernel struct on whichever target it's compiled for,
+/// without relying on an architecture-specific `repr(packed)` hack.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct EpollEvent(libc::epoll_event);
+
+impl EpollEvent {
+    /// Create a new `EpollEvent` from the given flags and user data
+    pub fn new(flags: EpollFlags, data: u64) -> EpollEvent {
+        EpollEvent(libc::epoll_event {
+            events: flags.bits() as u32,
+            u64: data,
+        })
+    }
+
+    /// Create an `EpollEvent` with no flags set and user data of 0
+    pub fn empty() -> EpollEvent {
+        EpollEvent(libc::epoll_event { events: 0, u64: 0 })
+    }
+
+    /// Returns the event flags
+    pub fn events(&self) -> EpollFlags {
+        EpollFlags(self.0.events as i32)
+    }
+
+    /// Returns the user data variable
+    pub fn data(&self) -> u64 {
+        self.0.u64
+    }
+}
+
+impl std::fmt::Debug for EpollEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EpollEvent")
+            .field("events", &self.events())
+            .field("data", &self.data())
+            .finish()
+    }
 }
 
 /// Create an epoll instance
 ///
 /// # Arguments
 ///
-/// `flags`:  flags to pass to `libc::epoll_create1`. Set flags to 0 for behavior equivalent to
-/// `epoll_create()`. Include `libc::EPOLL_CLOEXEC` in flags to set close-on-exec on the new file
-/// descriptor.
+/// `flags`:  flags to pass to `libc::epoll_create1`. Set flags to `EpollCreateFlags::empty()` for
+/// behavior equivalent to `epoll_create()`. Include `EpollCreateFlags::EPOLL_CLOEXEC` in flags to
+/// set close-on-exec on the new file descriptor.
 ///
 /// # Return Value
 ///
@@ -32,11 +292,11 @@ pub struct EpollEvent {
 /// See https://man7.org/linux/man-pages/man2/epoll_create1.2.html for complete documentation of the
 /// underlying C function that is called.
 ///
-pub fn epoll_create1(flags: i32) -> io::Result<RawFd> {
+pub fn epoll_create1(flags: EpollCreateFlags) -> io::Result<RawFd> {
     // On success, returns a file descriptor (a nonnegative integer).  On error, -1 is returned,
     // and errno is set to indicate the error
     unsafe {
-        let result = libc::epoll_create1(flags);
+        let result = libc::epoll_create1(flags.bits());
         if result < 0 {
             Err(Error::last_os_error())
         } else {
@@ -51,8 +311,7 @@ pub fn epoll_create1(flags: i32) -> io::Result<RawFd> {
 ///
 /// `epfd`: file descriptor to the epoll instance.
 ///
-/// `op`: operation to be performed on the target file descriptor. Valid values include
-/// `libc::EPOLL_CTL_ADD`, libc::EPOLL_CTL_DEL`, and `libc::EPOLL_CTL_MOD`.
+/// `op`: operation to be performed on the target file descriptor.
 ///
 /// `fd`: target file descriptor.
 ///
@@ -62,14 +321,14 @@ pub fn epoll_create1(flags: i32) -> io::Result<RawFd> {
 ///
 /// See https://man7.org/linux/man-pages/man2/epoll_ctl.2.html for complete documentation of the
 /// underlying C function that is called.
-pub fn epoll_ctl(epfd: RawFd, op: i32, fd: RawFd, mut event: EpollEvent) -> io::Result<()> {
+pub fn epoll_ctl(epfd: RawFd, op: EpollOp, fd: RawFd, mut event: EpollEvent) -> io::Result<()> {
     // cast event to mut pointer to libc::epoll_event
     let e = &mut event as *mut _ as *mut libc::epoll_event;
 
     // When successful, epoll_ctl() returns zero.  When an error occurs,
     // epoll_ctl() returns -1 and errno is set appropriately.
     unsafe {
-        let result = libc::epoll_ctl(epfd, op as i32, fd, e);
+        let result = libc::epoll_ctl(epfd, op.as_raw(), fd, e);
         if result < 0 {
             Err(Error::last_os_error())
         } else {